@@ -0,0 +1,86 @@
+// Main-thread helper for creating Amdusias AudioWorkletNodes.
+
+// Per-context setup (compiled module + loaded worklet), shared by all nodes
+const setups = new WeakMap();
+
+function setup(ctx, options) {
+  let pending = setups.get(ctx);
+  if (!pending) {
+    const wasmUrl = options.wasmUrl ?? new URL('./amdusias_web_bg.wasm', import.meta.url);
+    const workletUrl = options.workletUrl ?? new URL('./amdusias-worklet.js', import.meta.url);
+    pending = (async () => {
+      const module = options.module ?? await WebAssembly.compileStreaming(fetch(wasmUrl));
+      await ctx.audioWorklet.addModule(workletUrl);
+      return module;
+    })();
+    pending.catch(() => setups.delete(ctx));
+    setups.set(ctx, pending);
+  }
+  return pending;
+}
+
+/**
+ * Compiles the engine, loads the worklet module and creates the node.
+ *
+ * The module is compiled and the worklet loaded once per context; further
+ * nodes share the same WASM memory and sample pool. The node's registry ID
+ * is available as `node.amdusiasId` once the promise resolves.
+ *
+ * @param {BaseAudioContext} ctx
+ * @param {object} [options]
+ * @param {string | URL} [options.wasmUrl] URL of `amdusias_web_bg.wasm`
+ * @param {string | URL} [options.workletUrl] URL of `amdusias-worklet.js`
+ * @param {WebAssembly.Module} [options.module] Precompiled module (skips fetching)
+ * @returns {Promise<AudioWorkletNode>}
+ */
+export async function createAmdusiasNode(ctx, options = {}) {
+  const module = await setup(ctx, options);
+
+  const node = new AudioWorkletNode(ctx, 'amdusias-processor', {
+    numberOfInputs: 1,
+    numberOfOutputs: 1,
+    outputChannelCount: [2],
+    processorOptions: { module },
+  });
+
+  await new Promise((resolve, reject) => {
+    const onMessage = (event) => {
+      if (event.data?.type === 'ready') {
+        node.port.removeEventListener('message', onMessage);
+        node.amdusiasId = event.data.node_id;
+        resolve();
+      } else if (event.data?.type === 'error') {
+        node.port.removeEventListener('message', onMessage);
+        reject(new Error(event.data.message));
+      }
+    };
+    node.port.addEventListener('message', onMessage);
+    node.port.start();
+  });
+
+  return node;
+}
+
+/**
+ * Requests the current recording from a node and wraps it in a WAV Blob.
+ *
+ * Start and stop recording with the `record_start` and `record_stop`
+ * messages; the recording is kept until the next `record_start`.
+ *
+ * @param {AudioWorkletNode} node
+ * @param {'pcm16' | 'float32'} [format]
+ * @returns {Promise<Blob>}
+ */
+export function exportRecording(node, format = 'pcm16') {
+  return new Promise((resolve) => {
+    const onMessage = (event) => {
+      if (event.data?.type === 'recording') {
+        node.port.removeEventListener('message', onMessage);
+        resolve(new Blob([event.data.wav], { type: 'audio/wav' }));
+      }
+    };
+    node.port.addEventListener('message', onMessage);
+    node.port.start();
+    node.port.postMessage({ type: 'record_export', format });
+  });
+}
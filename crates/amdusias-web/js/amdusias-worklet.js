@@ -0,0 +1,123 @@
+// Amdusias AudioWorkletProcessor.
+//
+// Load with `audioContext.audioWorklet.addModule()` from the same directory
+// as the wasm-bindgen output (`amdusias_web.js`). The compiled
+// `WebAssembly.Module` is passed in `processorOptions.module`; see
+// `createAmdusiasNode()` in `amdusias-node.js`.
+//
+// All processors of a context share one AudioWorkletGlobalScope, so the
+// module is instantiated once and every node shares its memory and sample
+// pool. Post `{ type: 'dispose' }` to release a node's engine state.
+
+import './text-codec-polyfill.js';
+import { initSync, WorkletBridge } from './amdusias_web.js';
+
+const now = () => (globalThis.performance ? performance.now() : Date.now());
+
+class AmdusiasProcessor extends AudioWorkletProcessor {
+  constructor(options) {
+    super();
+    this.bridge = null;
+    this.disposed = false;
+    this.frames = 0;
+    this.interleaved = null;
+    this.result = null;
+    this.port.onmessage = (event) => this.handleMessage(event.data);
+
+    const module = options?.processorOptions?.module;
+    if (module) {
+      this.init(module);
+    }
+  }
+
+  init(module) {
+    try {
+      // No-op once the first processor in this scope has instantiated it
+      initSync({ module });
+      this.bridge = new WorkletBridge(sampleRate);
+      this.port.postMessage({ type: 'ready', node_id: this.bridge.node_id() });
+    } catch (err) {
+      this.port.postMessage({ type: 'error', message: String(err) });
+    }
+  }
+
+  handleMessage(data) {
+    if (data?.type === 'init') {
+      this.init(data.module);
+      return;
+    }
+    if (data?.type === 'dispose') {
+      this.bridge?.free();
+      this.bridge = null;
+      this.disposed = true;
+      return;
+    }
+    if (!this.bridge) return;
+
+    try {
+      const reply = this.bridge.handle_message(data);
+      if (reply === undefined) return;
+
+      if (reply.type === 'recording') {
+        // Transfer rather than copy the (potentially large) WAV bytes
+        this.port.postMessage(reply, [reply.wav.buffer]);
+      } else {
+        this.port.postMessage({ type: 'graph', reply });
+      }
+    } catch (err) {
+      this.port.postMessage({ type: 'error', message: String(err) });
+    }
+  }
+
+  ensureBuffers(frames) {
+    if (this.frames !== frames) {
+      this.frames = frames;
+      this.interleaved = new Float32Array(frames * 2);
+      this.result = new Float32Array(frames * 2);
+    }
+  }
+
+  process(inputs, outputs) {
+    if (this.disposed) return false;
+    if (!this.bridge) return true;
+
+    const input = inputs[0] ?? [];
+    const output = outputs[0];
+    if (!output || output.length === 0) return true;
+
+    const frames = output[0].length;
+    this.ensureBuffers(frames);
+
+    // Interleave input channels (mono is duplicated, no input is silence)
+    const left = input[0];
+    const right = input[1] ?? left;
+    const interleaved = this.interleaved;
+    for (let i = 0; i < frames; i++) {
+      interleaved[i * 2] = left ? left[i] : 0;
+      interleaved[i * 2 + 1] = right ? right[i] : 0;
+    }
+
+    const start = now();
+    this.bridge.process(interleaved, this.result);
+
+    const stats = this.bridge.record_quantum(now() - start, currentFrame, frames);
+    if (stats !== undefined) this.port.postMessage(stats);
+
+    if (this.bridge.has_tap_frames()) {
+      for (const frame of this.bridge.take_tap_frames()) {
+        this.port.postMessage(frame, [frame.data.buffer]);
+      }
+    }
+
+    // De-interleave output
+    const result = this.result;
+    for (let i = 0; i < frames; i++) {
+      output[0][i] = result[i * 2];
+      if (output[1]) output[1][i] = result[i * 2 + 1];
+    }
+
+    return true;
+  }
+}
+
+registerProcessor('amdusias-processor', AmdusiasProcessor);
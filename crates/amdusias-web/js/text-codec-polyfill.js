@@ -0,0 +1,63 @@
+// Minimal UTF-8 TextEncoder/TextDecoder for AudioWorkletGlobalScope.
+//
+// Some browsers do not expose the Encoding API inside worklets, but the
+// wasm-bindgen glue constructs both at module load. This module must be
+// imported before `amdusias_web.js`.
+
+if (typeof globalThis.TextDecoder === 'undefined') {
+  globalThis.TextDecoder = class TextDecoder {
+    decode(bytes) {
+      if (!bytes) return '';
+      let out = '';
+      for (let i = 0; i < bytes.length; ) {
+        const b = bytes[i++];
+        let cp;
+        if (b < 0x80) {
+          cp = b;
+        } else if (b < 0xe0) {
+          cp = ((b & 0x1f) << 6) | (bytes[i++] & 0x3f);
+        } else if (b < 0xf0) {
+          cp = ((b & 0x0f) << 12) | ((bytes[i++] & 0x3f) << 6) | (bytes[i++] & 0x3f);
+        } else {
+          cp = ((b & 0x07) << 18) | ((bytes[i++] & 0x3f) << 12) |
+            ((bytes[i++] & 0x3f) << 6) | (bytes[i++] & 0x3f);
+        }
+        out += String.fromCodePoint(cp);
+      }
+      return out;
+    }
+  };
+}
+
+if (typeof globalThis.TextEncoder === 'undefined') {
+  globalThis.TextEncoder = class TextEncoder {
+    get encoding() {
+      return 'utf-8';
+    }
+
+    encode(str = '') {
+      const out = [];
+      for (const ch of str) {
+        const cp = ch.codePointAt(0);
+        if (cp < 0x80) {
+          out.push(cp);
+        } else if (cp < 0x800) {
+          out.push(0xc0 | (cp >> 6), 0x80 | (cp & 0x3f));
+        } else if (cp < 0x10000) {
+          out.push(0xe0 | (cp >> 12), 0x80 | ((cp >> 6) & 0x3f), 0x80 | (cp & 0x3f));
+        } else {
+          out.push(0xf0 | (cp >> 18), 0x80 | ((cp >> 12) & 0x3f),
+            0x80 | ((cp >> 6) & 0x3f), 0x80 | (cp & 0x3f));
+        }
+      }
+      return new Uint8Array(out);
+    }
+
+    encodeInto(str, dest) {
+      const bytes = this.encode(str);
+      const written = Math.min(bytes.length, dest.length);
+      dest.set(bytes.subarray(0, written));
+      return { read: str.length, written };
+    }
+  };
+}